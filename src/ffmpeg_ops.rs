@@ -16,6 +16,40 @@ use crate::*;
 
 const FFPROBE_TIMEOUT_SECS: usize = 60;
 
+// Where ffmpeg should read its input from. A `Path` is handed to ffmpeg directly
+// (and can be probed by ffprobe), whereas a `Reader` is streamed into the child's
+// stdin via `-i -` — useful for in-memory or network-sourced video that would
+// otherwise have to be spilled to a temp file first. ffprobe cannot seek a pipe,
+// so in the `Reader` case the resolution must be supplied explicitly on the
+// builder and `VideoInfo` is left mostly empty.
+pub enum Source {
+    Path(PathBuf),
+    Reader(Box<dyn Read + Send>),
+}
+
+impl From<PathBuf> for Source {
+    fn from(path: PathBuf) -> Self {
+        Source::Path(path)
+    }
+}
+
+impl From<&Path> for Source {
+    fn from(path: &Path) -> Self {
+        Source::Path(path.to_path_buf())
+    }
+}
+
+impl Source {
+    // Reduce the source to the ffmpeg input argument (`-` for a reader) plus an
+    // optional reader to be streamed into the child's stdin.
+    fn into_input(self) -> (PathBuf, Option<Box<dyn Read + Send>>) {
+        match self {
+            Source::Path(path) => (path, None),
+            Source::Reader(reader) => (PathBuf::from("-"), Some(reader)),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct FfmpegFrames {
     x: u32,
@@ -84,19 +118,29 @@ impl Drop for FfmpegFrames {
 }
 
 pub struct FfmpegFrameReaderBuilder {
-    src_path: PathBuf,
+    source: Source,
     fps: Option<String>,
+    scale: Option<String>,
+    seek_secs: Option<f64>,
+    to_secs: Option<f64>,
+    out_resolution: Option<(u32, u32)>,
     num_frames: Option<u32>,
     timeout_secs: Option<u64>,
+    parallel: Option<usize>,
 }
 
 impl FfmpegFrameReaderBuilder {
-    pub fn new(src_path: PathBuf) -> Self {
+    pub fn new(source: impl Into<Source>) -> Self {
         Self {
-            src_path,
+            source: source.into(),
             fps: None,
+            scale: None,
+            seek_secs: None,
+            to_secs: None,
+            out_resolution: None,
             num_frames: None,
             timeout_secs: None,
+            parallel: None,
         }
     }
 
@@ -105,6 +149,30 @@ impl FfmpegFrameReaderBuilder {
         self
     }
 
+    // An ffmpeg `scale=...` filter body (without the leading `scale=`), applied
+    // alongside any `fps` filter. When the scaled output differs from the source
+    // resolution, pair this with `out_resolution` so frames are read at the right
+    // size.
+    pub fn scale(&mut self, scale: impl AsRef<str>) -> &mut Self {
+        self.scale = Some(scale.as_ref().to_string());
+        self
+    }
+
+    // Seek this many seconds into the source before decoding (fast `-ss` seek
+    // placed ahead of `-i`).
+    pub fn seek_secs(&mut self, seek_secs: f64) -> &mut Self {
+        self.seek_secs = Some(seek_secs);
+        self
+    }
+
+    // Override the resolution used to size the raw-frame buffer. Needed whenever a
+    // `scale` filter changes the decoded frame dimensions away from
+    // `VideoInfo::resolution`.
+    pub fn out_resolution(&mut self, resolution: (u32, u32)) -> &mut Self {
+        self.out_resolution = Some(resolution);
+        self
+    }
+
     pub fn num_frames(&mut self, num_frames: u32) -> &mut Self {
         self.num_frames = Some(num_frames);
         self
@@ -115,10 +183,52 @@ impl FfmpegFrameReaderBuilder {
         self
     }
 
-    pub fn spawn(&self) -> Result<(FfmpegFrames, VideoInfo), FfmpegErrorKind> {
+    pub fn spawn(&mut self) -> Result<(FfmpegFrames, VideoInfo), FfmpegErrorKind> {
+        let stats = self.spawn_child("rgb24", "image2pipe")?;
+        let child = stats.0;
+        let stats = stats.1;
+
+        let (x, y) = self.out_resolution.unwrap_or(stats.resolution);
+
+        let frame_iterator = FfmpegFrames {
+            x,
+            y,
+            child,
+            num_frames: self.num_frames.unwrap_or(u32::MAX),
+            frames_read: 0,
+            timeout_time: SystemTime::now()
+                + Duration::from_secs(self.timeout_secs.unwrap_or(u32::MAX as u64)), // (just in case u64::MAX has wraparound issues)
+            finished: false,
+        };
+
+        Ok((frame_iterator, stats))
+    }
+
+    // Spawn the ffmpeg child that decodes to raw frames in `pix_fmt`, resolve the
+    // video info, and (for a reader source) start pumping stdin. Shared by the
+    // rgb24 and yuv420p decode paths.
+    fn spawn_child(
+        &mut self,
+        pix_fmt: &str,
+        format: &str,
+    ) -> Result<(Child, VideoInfo), FfmpegErrorKind> {
         //we also need to find out the resolution of the video so that stdout can be converted into frames.
-        let stats =
-            VideoInfo::new(&self.src_path).map_err(|e| FfmpegErrorKind::Io(e.to_string()))?;
+        //a path can be probed directly; a reader cannot be seeked by ffprobe, so the
+        //resolution must have been supplied explicitly via `out_resolution`.
+        let stats = match &self.source {
+            Source::Path(path) => {
+                VideoInfo::new(path).map_err(|e| FfmpegErrorKind::Io(e.to_string()))?
+            }
+            Source::Reader(_) => {
+                let (x, y) = self
+                    .out_resolution
+                    .ok_or(FfmpegErrorKind::InvalidResolution)?;
+                VideoInfo {
+                    resolution: (x, y),
+                    ..Default::default()
+                }
+            }
+        };
 
         //bail out if we get invalid dimensions.
         let (x, y) = stats.resolution();
@@ -126,11 +236,45 @@ impl FfmpegFrameReaderBuilder {
             return Err(FfmpegErrorKind::InvalidResolution);
         }
 
-        let fps_string: String;
-        let fps_arg = match self.fps {
-            Some(ref fps) => {
-                fps_string = format!("fps={}", fps);
-                vec![OsStr::new("-vf"), OsStr::new(&fps_string)]
+        //ffmpeg reads a path directly, or `-` when the bytes are streamed in on stdin.
+        let input_path: PathBuf = match &self.source {
+            Source::Path(path) => path.clone(),
+            Source::Reader(_) => PathBuf::from("-"),
+        };
+
+        //combine any fps and scale filters into a single filtergraph.
+        let mut filters = vec![];
+        if let Some(ref fps) = self.fps {
+            filters.push(format!("fps={}", fps));
+        }
+        if let Some(ref scale) = self.scale {
+            filters.push(format!("scale={}", scale));
+        }
+        let filter_string = filters.join(",");
+        let filter_arg = if filter_string.is_empty() {
+            vec![]
+        } else {
+            vec![OsStr::new("-vf"), OsStr::new(&filter_string)]
+        };
+
+        let seek_string: String;
+        let seek_arg = match self.seek_secs {
+            Some(seek_secs) => {
+                seek_string = format!("{}", seek_secs);
+                vec![OsStr::new("-ss"), OsStr::new(&seek_string)]
+            }
+            None => vec![],
+        };
+
+        //stop time is an input option placed alongside `-ss` (before `-i`), so it is
+        //interpreted against the source timeline. Emitting it after `-i` would make
+        //ffmpeg treat it as a duration from the seek point, which overlaps the ranges
+        //handed to parallel workers.
+        let to_string: String;
+        let to_arg = match self.to_secs {
+            Some(to_secs) => {
+                to_string = format!("{}", to_secs);
+                vec![OsStr::new("-to"), OsStr::new(&to_string)]
             }
             None => vec![],
         };
@@ -149,48 +293,550 @@ impl FfmpegFrameReaderBuilder {
             OsStr::new("-hide_banner"),
             OsStr::new("-loglevel"), OsStr::new("warning"),
             OsStr::new("-nostats"),
-            // OsStr::new("-ss"),       OsStr::new("00:00:30"),        
-            OsStr::new("-i"),        OsStr::new(&self.src_path),
         ];
 
-        args.extend(fps_arg);
+        args.extend(seek_arg);
+        args.extend(to_arg);
+
+        #[rustfmt::skip]
+        args.extend(&[
+            OsStr::new("-i"),        OsStr::new(&input_path),
+        ]);
+
+        args.extend(filter_arg);
         args.extend(num_frames_arg);
 
         #[rustfmt::skip]
         args.extend(&[
-            OsStr::new("-pix_fmt"),  OsStr::new("rgb24"),
+            OsStr::new("-pix_fmt"),  OsStr::new(pix_fmt),
             OsStr::new("-c:v"),      OsStr::new("rawvideo"),
-            OsStr::new("-f"),        OsStr::new("image2pipe"),
+            OsStr::new("-f"),        OsStr::new(format),
             OsStr::new("-")
         ]);
 
-        //println!("{:?}", args);
-
         let mut child = spawn_ffmpeg_command(Ffmpeg, &args, true)?;
 
         //Prevent possible lockup if stderr gets full by dropping the
         //handle from our side
         std::mem::drop(child.stderr.take());
 
-        let (x, y) = stats.resolution;
+        //when streaming from a reader, pump its bytes into the child's stdin on a
+        //dedicated thread while frames are read back from stdout. Dropping the stdin
+        //handle at the end of the copy signals EOF to ffmpeg.
+        if let Source::Reader(reader) = &mut self.source {
+            let mut reader = std::mem::replace(reader, Box::new(std::io::empty()));
+            if let Some(mut stdin) = child.stdin.take() {
+                std::thread::spawn(move || {
+                    let _copy_error = std::io::copy(&mut reader, &mut stdin);
+                });
+            }
+        }
 
-        let frame_iterator = FfmpegFrames {
-            x,
-            y,
-            child,
-            num_frames: self.num_frames.unwrap_or(u32::MAX),
-            frames_read: 0,
-            timeout_time: SystemTime::now()
-                + Duration::from_secs(self.timeout_secs.unwrap_or(u32::MAX as u64)), // (just in case u64::MAX has wraparound issues)
-            finished: false,
+        Ok((child, stats))
+    }
+
+    // Decode the video straight into planar `YuvFrames` (yuv420p) instead of
+    // packed RGB, avoiding the RGB round-trip for luma-only work like letterbox
+    // detection. Reads up to `num_frames` frames (or until EOF), honouring the
+    // configured timeout.
+    pub fn spawn_yuv(&mut self) -> Result<(YuvFrames, VideoInfo), FfmpegErrorKind> {
+        let (mut child, stats) = self.spawn_child("yuv420p", "rawvideo")?;
+
+        let (x, y) = self.out_resolution.unwrap_or(stats.resolution);
+        let max_frames = self.num_frames.unwrap_or(u32::MAX);
+        let timeout_time =
+            SystemTime::now() + Duration::from_secs(self.timeout_secs.unwrap_or(u32::MAX as u64));
+
+        //yuv420p: full-res luma plane, half-res (rounded up) chroma planes.
+        let (cw, ch) = ((x + 1) / 2, (y + 1) / 2);
+        let frame_len = (x * y + 2 * cw * ch) as usize;
+
+        let mut stdout = child.stdout.take().expect("Failed to obtain stdout");
+        let mut frames = vec![];
+        let mut buf = vec![0u8; frame_len];
+
+        while (frames.len() as u32) < max_frames {
+            if SystemTime::now() > timeout_time {
+                break;
+            }
+
+            match read_full(&mut stdout, &mut buf) {
+                //complete frame decoded: carve it into planes.
+                Ok(true) => {
+                    let y_len = (x * y) as usize;
+                    let c_len = (cw * ch) as usize;
+                    let y_plane = YuvPlane::new(buf[..y_len].to_vec(), x, y, x);
+                    let u_plane =
+                        YuvPlane::new(buf[y_len..y_len + c_len].to_vec(), cw, ch, cw);
+                    let v_plane = YuvPlane::new(
+                        buf[y_len + c_len..y_len + 2 * c_len].to_vec(),
+                        cw,
+                        ch,
+                        cw,
+                    );
+                    frames.push(YuvFrame::new(
+                        y_plane,
+                        u_plane,
+                        v_plane,
+                        Subsampling::yuv420(),
+                    ));
+                }
+                //EOF or error: stop.
+                Ok(false) | Err(_) => break,
+            }
+        }
+
+        let _kill_error = child.kill();
+        let _wait_error = child.wait();
+
+        Ok((YuvFrames::from_frames(frames), stats))
+    }
+
+    // Number of worker processes to split whole-file extraction across. Passing 0
+    // (or never calling this) falls back to `std::thread::available_parallelism`.
+    pub fn parallel(&mut self, workers: usize) -> &mut Self {
+        self.parallel = Some(workers);
+        self
+    }
+
+    // Decode the whole file using several seeking ffmpeg processes in parallel,
+    // one per equal time range, and merge the frames back in timestamp order. This
+    // is a near-linear speedup for whole-file sampling on multicore machines.
+    //
+    // Only supported for a `Source::Path`, since a reader cannot be seeked.
+    pub fn spawn_parallel(
+        &mut self,
+    ) -> Result<(Vec<image::RgbImage>, VideoInfo), FfmpegErrorKind> {
+        let path = match &self.source {
+            Source::Path(path) => path.clone(),
+            Source::Reader(_) => return Err(FfmpegErrorKind::InvalidResolution),
         };
 
-        //Ok((frames, stats))
-        Ok((frame_iterator, stats))
+        let stats = VideoInfo::new(&path).map_err(|e| FfmpegErrorKind::Io(e.to_string()))?;
+        let (x, y) = stats.resolution();
+        if x == 0 || y == 0 {
+            return Err(FfmpegErrorKind::InvalidResolution);
+        }
+
+        let workers = self
+            .parallel
+            .filter(|&w| w > 0)
+            .unwrap_or_else(|| {
+                std::thread::available_parallelism()
+                    .map(|n| n.get())
+                    .unwrap_or(1)
+            })
+            .max(1);
+
+        //each worker decodes one equal slice of the duration.
+        let chunk = stats.duration() / workers as f64;
+
+        let mut handles = Vec::with_capacity(workers);
+        for i in 0..workers {
+            let start = i as f64 * chunk;
+            let end = start + chunk;
+
+            //partition any configured `num_frames` cap across the workers so the merged
+            //output honours the caller's total, handing the remainder to the earliest
+            //ranges to keep the split deterministic.
+            let worker_num_frames = self.num_frames.map(|total| {
+                let base = total / workers as u32;
+                let extra = if (i as u32) < total % workers as u32 {
+                    1
+                } else {
+                    0
+                };
+                base + extra
+            });
+
+            let mut worker = FfmpegFrameReaderBuilder {
+                source: Source::Path(path.clone()),
+                fps: self.fps.clone(),
+                scale: self.scale.clone(),
+                seek_secs: Some(start),
+                to_secs: Some(end),
+                out_resolution: self.out_resolution,
+                num_frames: worker_num_frames,
+                timeout_secs: self.timeout_secs,
+                parallel: None,
+            };
+
+            //tag each worker's output with its range index so the merge is deterministic.
+            let handle = std::thread::spawn(
+                move || -> Result<(usize, Vec<image::RgbImage>), FfmpegErrorKind> {
+                    let (frames, _stats) = worker.spawn()?;
+                    Ok((i, frames.collect()))
+                },
+            );
+            handles.push(handle);
+        }
+
+        let mut tagged = Vec::with_capacity(workers);
+        for handle in handles {
+            let result = handle
+                .join()
+                .map_err(|_| FfmpegErrorKind::Io("worker thread panicked".to_string()))?;
+            tagged.push(result?);
+        }
+        tagged.sort_by_key(|(i, _)| *i);
+
+        let frames = tagged.into_iter().flat_map(|(_, frames)| frames).collect();
+        Ok((frames, stats))
+    }
+}
+
+// Builder for `FfmpegFrameWriter`, the inverse of `FfmpegFrames`: it feeds raw
+// `rgb24` frames into ffmpeg and encodes them to an output file.
+pub struct FfmpegFrameWriterBuilder {
+    out_path: PathBuf,
+    width: u32,
+    height: u32,
+    codec: Option<String>,
+    crf: Option<u32>,
+    pix_fmt: Option<String>,
+    fps: Option<String>,
+    timeout_secs: Option<u64>,
+}
+
+impl FfmpegFrameWriterBuilder {
+    pub fn new(out_path: PathBuf, width: u32, height: u32) -> Self {
+        Self {
+            out_path,
+            width,
+            height,
+            codec: None,
+            crf: None,
+            pix_fmt: None,
+            fps: None,
+            timeout_secs: None,
+        }
+    }
+
+    // Output video codec passed as `-c:v`. Defaults to `libx264`.
+    pub fn codec(&mut self, codec: impl AsRef<str>) -> &mut Self {
+        self.codec = Some(codec.as_ref().to_string());
+        self
+    }
+
+    // Constant-rate-factor / quality value passed as `-crf`.
+    pub fn crf(&mut self, crf: u32) -> &mut Self {
+        self.crf = Some(crf);
+        self
+    }
+
+    // Output pixel format passed as `-pix_fmt` (e.g. `yuv420p`).
+    pub fn pix_fmt(&mut self, pix_fmt: impl AsRef<str>) -> &mut Self {
+        self.pix_fmt = Some(pix_fmt.as_ref().to_string());
+        self
+    }
+
+    // Frame rate of both the raw input stream and the encoded output.
+    pub fn fps(&mut self, fps: impl AsRef<str>) -> &mut Self {
+        self.fps = Some(fps.as_ref().to_string());
+        self
+    }
+
+    pub fn timeout_secs(&mut self, timeout_secs: u64) -> &mut Self {
+        self.timeout_secs = Some(timeout_secs);
+        self
+    }
+
+    pub fn spawn(&self) -> Result<FfmpegFrameWriter, FfmpegErrorKind> {
+        if self.width == 0 || self.height == 0 {
+            return Err(FfmpegErrorKind::InvalidResolution);
+        }
+
+        let size_string = format!("{}x{}", self.width, self.height);
+        let fps_string = self.fps.clone().unwrap_or_else(|| "30".to_string());
+        let codec_string = self.codec.clone().unwrap_or_else(|| "libx264".to_string());
+
+        #[rustfmt::skip]
+        let mut args = vec![
+            OsStr::new("-hide_banner"),
+            OsStr::new("-loglevel"),  OsStr::new("warning"),
+            OsStr::new("-nostats"),
+            OsStr::new("-f"),         OsStr::new("rawvideo"),
+            OsStr::new("-pix_fmt"),   OsStr::new("rgb24"),
+            OsStr::new("-s"),         OsStr::new(&size_string),
+            OsStr::new("-r"),         OsStr::new(&fps_string),
+            OsStr::new("-i"),         OsStr::new("-"),
+            OsStr::new("-c:v"),       OsStr::new(&codec_string),
+        ];
+
+        let crf_string: String;
+        if let Some(crf) = self.crf {
+            crf_string = crf.to_string();
+            args.extend(&[OsStr::new("-crf"), OsStr::new(&crf_string)]);
+        }
+
+        if let Some(ref pix_fmt) = self.pix_fmt {
+            args.extend(&[OsStr::new("-pix_fmt"), OsStr::new(pix_fmt)]);
+        }
+
+        #[rustfmt::skip]
+        args.extend(&[
+            OsStr::new("-y"),
+            OsStr::new(&self.out_path),
+        ]);
+
+        let mut child = spawn_ffmpeg_command(Ffmpeg, &args, true)?;
+
+        //Prevent possible lockup if stderr gets full by dropping the
+        //handle from our side
+        std::mem::drop(child.stderr.take());
+
+        Ok(FfmpegFrameWriter {
+            child: Some(child),
+            width: self.width,
+            height: self.height,
+            timeout_secs: self.timeout_secs.unwrap_or(FFPROBE_TIMEOUT_SECS as u64) as usize,
+        })
+    }
+}
+
+// Encodes a stream of `image::RgbImage` frames into a video file. Call
+// `write_frame` for each frame, then `finish` to flush and reap the process.
+#[derive(Debug)]
+pub struct FfmpegFrameWriter {
+    child: Option<Child>,
+    width: u32,
+    height: u32,
+    timeout_secs: usize,
+}
+
+impl FfmpegFrameWriter {
+    pub fn write_frame(&mut self, frame: &image::RgbImage) -> Result<(), FfmpegErrorKind> {
+        if frame.width() != self.width || frame.height() != self.height {
+            return Err(FfmpegErrorKind::InvalidResolution);
+        }
+
+        let child = self
+            .child
+            .as_mut()
+            .ok_or_else(|| FfmpegErrorKind::Io("frame writer already finished".to_string()))?;
+        let stdin = child
+            .stdin
+            .as_mut()
+            .ok_or_else(|| FfmpegErrorKind::Io("frame writer stdin closed".to_string()))?;
+
+        stdin
+            .write_all(frame.as_raw())
+            .map_err(|e| FfmpegErrorKind::Io(format!("{:?}", e.kind())))
+    }
+
+    // Close stdin so ffmpeg flushes its output, then wait for it to exit through
+    // the shared timeout-guarded waiting logic.
+    pub fn finish(mut self) -> Result<(), FfmpegErrorKind> {
+        let mut child = self
+            .child
+            .take()
+            .ok_or_else(|| FfmpegErrorKind::Io("frame writer already finished".to_string()))?;
+
+        //closing stdin signals EOF so ffmpeg finishes encoding.
+        std::mem::drop(child.stdin.take());
+
+        match wait_ffmpeg_child(child, self.timeout_secs) {
+            Err(e) => match e.kind() {
+                std::io::ErrorKind::NotFound => Err(FfmpegErrorKind::FfmpegNotFound),
+                _ => Err(FfmpegErrorKind::Io(format!("{:?}", e.kind()))),
+            },
+            Ok(status) if status.success() => Ok(()),
+            Ok(status) => Err(FfmpegErrorKind::FfmpegInternal(format!(
+                "frame writer exited with {}",
+                status
+            ))),
+        }
+    }
+}
+
+// Reap the child if the writer is dropped without calling `finish`, matching the
+// zombie-avoidance behaviour of `FfmpegFrames`.
+impl Drop for FfmpegFrameWriter {
+    fn drop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _kill_error = child.kill();
+            let _wait_error = child.wait();
+        }
     }
 }
 
-pub fn get_video_stats<P: AsRef<Path>>(src_path: P) -> Result<String, FfmpegErrorKind> {
+// How a thumbnail should be sized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThumbnailSize {
+    // Scale the longest edge to this many pixels, preserving aspect ratio.
+    Scale(u32),
+    // Scale to exactly these dimensions, ignoring aspect ratio.
+    Exact(u32, u32),
+}
+
+// Grab a single representative frame and return it resized according to `size`.
+// The scaling is performed inside ffmpeg (via a `scale` filter) rather than by
+// decoding full-resolution frames and resizing in Rust, and a seek to 10% of the
+// duration avoids a black intro frame.
+pub fn thumbnail(src_path: PathBuf, size: ThumbnailSize) -> Result<image::RgbImage, FfmpegErrorKind> {
+    let stats = VideoInfo::new(&src_path).map_err(|e| FfmpegErrorKind::Io(e.to_string()))?;
+
+    let (src_w, src_h) = stats.resolution();
+    if src_w == 0 || src_h == 0 {
+        return Err(FfmpegErrorKind::InvalidResolution);
+    }
+
+    //compute the exact output resolution so the raw-frame buffer matches ffmpeg's
+    //scaled output.
+    let (out_w, out_h) = match size {
+        ThumbnailSize::Exact(w, h) => (w, h),
+        ThumbnailSize::Scale(longest) => {
+            if src_w >= src_h {
+                let h = ((longest as u64 * src_h as u64) / src_w as u64) as u32;
+                (longest, h.max(1))
+            } else {
+                let w = ((longest as u64 * src_w as u64) / src_h as u64) as u32;
+                (w.max(1), longest)
+            }
+        }
+    };
+
+    let mut builder = FfmpegFrameReaderBuilder::new(src_path);
+    builder
+        .seek_secs(stats.duration() * 0.10)
+        .scale(format!("{}:{}", out_w, out_h))
+        .out_resolution((out_w, out_h))
+        .num_frames(1);
+
+    let (frames, _stats) = builder.spawn()?;
+
+    frames.into_iter().next().ok_or(FfmpegErrorKind::InvalidResolution)
+}
+
+// A detected scene cut, as both a presentation timestamp and the frame index it
+// falls on (derived from the video's frame rate).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SceneChange {
+    pub time: f64,
+    pub frame: u64,
+}
+
+impl SceneChange {
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+}
+
+// Builder for scene-change detection, sitting alongside `FfmpegFrameReaderBuilder`.
+pub struct FfmpegSceneChangeBuilder {
+    src_path: PathBuf,
+    threshold: f64,
+    limit: Option<usize>,
+    timeout_secs: Option<u64>,
+}
+
+impl FfmpegSceneChangeBuilder {
+    pub fn new(src_path: PathBuf) -> Self {
+        Self {
+            src_path,
+            threshold: 0.4,
+            limit: None,
+            timeout_secs: None,
+        }
+    }
+
+    // Scene-score threshold in `0.0..=1.0`; higher means fewer, stronger cuts.
+    pub fn threshold(&mut self, threshold: f64) -> &mut Self {
+        self.threshold = threshold.clamp(0.0, 1.0);
+        self
+    }
+
+    // Cap the number of returned cuts.
+    pub fn limit(&mut self, limit: usize) -> &mut Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    // Override the subprocess timeout (full-file analysis can outlast the 60s default).
+    pub fn timeout_secs(&mut self, timeout_secs: u64) -> &mut Self {
+        self.timeout_secs = Some(timeout_secs);
+        self
+    }
+
+    pub fn detect(&self) -> Result<Vec<SceneChange>, FfmpegErrorKind> {
+        let stats =
+            VideoInfo::new(&self.src_path).map_err(|e| FfmpegErrorKind::Io(e.to_string()))?;
+
+        let filter = format!(
+            "select='gt(scene,{})',metadata=print",
+            self.threshold
+        );
+
+        #[rustfmt::skip]
+        let args = &[
+            OsStr::new("-hide_banner"),
+            OsStr::new("-i"),  OsStr::new(&self.src_path),
+            OsStr::new("-vf"), OsStr::new(&filter),
+            OsStr::new("-an"),
+            OsStr::new("-f"),  OsStr::new("null"),
+            OsStr::new("-"),
+        ];
+
+        let timeout = self.timeout_secs.unwrap_or(FFPROBE_TIMEOUT_SECS as u64) as usize;
+        let stderr = run_ffmpeg_command(Ffmpeg, args, false, timeout, None)?.stderr;
+        let stderr = String::from_utf8_lossy(&stderr);
+
+        let cuts = parse_scene_changes(&stderr, stats.frame_rate(), self.limit);
+        Ok(cuts)
+    }
+}
+
+// Parse the `metadata=print` output, pairing each `lavfi.scene_score` entry with
+// the `pts_time` of the frame it belongs to. An empty result means no cuts were
+// found, in which case the implicit start at 0.0 is returned.
+fn parse_scene_changes(stderr: &str, fps: f64, limit: Option<usize>) -> Vec<SceneChange> {
+    let mut cuts = vec![];
+    let mut last_pts: Option<f64> = None;
+
+    for line in stderr.lines() {
+        if let Some(idx) = line.find("pts_time:") {
+            let rest = &line[idx + "pts_time:".len()..];
+            let num: String = rest.chars().take_while(|c| !c.is_whitespace()).collect();
+            if let Ok(time) = num.parse::<f64>() {
+                last_pts = Some(time);
+            }
+        }
+
+        if line.contains("lavfi.scene_score") {
+            if let Some(time) = last_pts.take() {
+                cuts.push(SceneChange {
+                    time,
+                    frame: (time * fps).round() as u64,
+                });
+            }
+        }
+    }
+
+    if cuts.is_empty() {
+        cuts.push(SceneChange {
+            time: 0.0,
+            frame: 0,
+        });
+    }
+
+    if let Some(limit) = limit {
+        cuts.truncate(limit);
+    }
+
+    cuts
+}
+
+// Convenience wrapper around `FfmpegSceneChangeBuilder` using default settings.
+pub fn detect_scene_changes(src_path: PathBuf) -> Result<Vec<SceneChange>, FfmpegErrorKind> {
+    FfmpegSceneChangeBuilder::new(src_path).detect()
+}
+
+pub fn get_video_stats(src: impl Into<Source>) -> Result<String, FfmpegErrorKind> {
+    let (input_path, stdin_source) = src.into().into_input();
+
     let args = &[
         OsStr::new("-v"),
         OsStr::new("quiet"),
@@ -198,17 +844,18 @@ pub fn get_video_stats<P: AsRef<Path>>(src_path: P) -> Result<String, FfmpegErro
         OsStr::new("-show_streams"),
         OsStr::new("-print_format"),
         OsStr::new("json"),
-        OsStr::new(src_path.as_ref()),
+        OsStr::new(&input_path),
     ];
 
-    let stdout = run_ffmpeg_command(Ffprobe, args, true)?.stdout;
+    let stdout = run_ffmpeg_command(Ffprobe, args, true, FFPROBE_TIMEOUT_SECS, stdin_source)?.stdout;
 
     String::from_utf8(stdout).map_err(|_| Utf8Conversion)
 }
 
-pub fn is_video_file<P: AsRef<Path>>(src_path: P) -> Result<bool, FfmpegErrorKind> {
-    fn get_ffprobe_output<P: AsRef<Path>>(src_path: P) -> Result<String, FfmpegErrorKind> {
+pub fn is_video_file(src: impl Into<Source>) -> Result<bool, FfmpegErrorKind> {
+    fn get_ffprobe_output(source: Source) -> Result<String, FfmpegErrorKind> {
         //"ffprobe -v error -select_streams v -show_entries stream=codec_type,codec_name,duration -of compact=p=0:nk=1 {}"
+        let (input_path, stdin_source) = source.into_input();
 
         #[rustfmt::skip]
         let args = &[
@@ -216,17 +863,17 @@ pub fn is_video_file<P: AsRef<Path>>(src_path: P) -> Result<bool, FfmpegErrorKin
             OsStr::new("-select_streams"), OsStr::new("v"),
             OsStr::new("-show_entries"),   OsStr::new("stream=codec_type,codec_name,duration"),
             OsStr::new("-of"),             OsStr::new("compact=p=0:nk=1"),
-            OsStr::new(src_path.as_ref())
+            OsStr::new(&input_path)
         ];
 
-        run_ffmpeg_command(Ffprobe, args, true).and_then(|output| {
+        run_ffmpeg_command(Ffprobe, args, true, FFPROBE_TIMEOUT_SECS, stdin_source).and_then(|output| {
             String::from_utf8(output.stdout)
                 .map_err(|_| Utf8Conversion)
                 .map(|s| s.trim().to_string())
         })
     }
 
-    let streams_string = get_ffprobe_output(src_path.as_ref())?;
+    let streams_string = get_ffprobe_output(src.into())?;
 
     let mut fields_iter = streams_string.split('|');
 
@@ -252,12 +899,12 @@ pub fn is_video_file<P: AsRef<Path>>(src_path: P) -> Result<bool, FfmpegErrorKin
 
 pub fn ffmpeg_and_ffprobe_are_callable() -> bool {
     //check ffprobe is callable.
-    if run_ffmpeg_command(Ffprobe, &[OsStr::new("-version")], true).is_err() {
+    if run_ffmpeg_command(Ffprobe, &[OsStr::new("-version")], true, FFPROBE_TIMEOUT_SECS, None).is_err() {
         return false;
     }
 
     //now ffmpeg.
-    if run_ffmpeg_command(Ffmpeg, &[OsStr::new("-version")], true).is_err() {
+    if run_ffmpeg_command(Ffmpeg, &[OsStr::new("-version")], true, FFPROBE_TIMEOUT_SECS, None).is_err() {
         return false;
     }
 
@@ -311,8 +958,57 @@ fn spawn_ffmpeg_command(
     })
 }
 
+// Wait for a spawned ffmpeg/ffprobe child to exit, polling quickly for the first
+// second and then once a second, giving up after `timeout_secs`.
+fn wait_ffmpeg_child(mut child: Child, timeout_secs: usize) -> std::io::Result<ExitStatus> {
+    let max_initial_fast_counts = 100;
+    let mut initial_fast_counts = 0;
+    let mut timeout_counter_secs = 0;
+    let mut maybe_status;
+    while timeout_counter_secs < timeout_secs {
+        maybe_status = child.try_wait();
+        match maybe_status {
+            Err(e) => return Err(e),
+            Ok(None) => {
+                if initial_fast_counts < max_initial_fast_counts {
+                    std::thread::sleep(Duration::from_millis(10));
+                    initial_fast_counts += 1;
+                    if initial_fast_counts == max_initial_fast_counts {
+                        timeout_counter_secs += 1;
+                    }
+                } else {
+                    std::thread::sleep(Duration::from_millis(1_000));
+                    timeout_counter_secs += 1;
+                }
+            }
+            Ok(Some(s)) => return Ok(s),
+        }
+    }
+
+    //timed out: kill the child so its pipes close and any reader threads unblock,
+    //rather than leaving the process running and the caller wedged on a full pipe.
+    let _kill_error = child.kill();
+    let _reap_error = child.wait();
+    Err(std::io::Error::from(std::io::ErrorKind::TimedOut))
+}
+
+// Read exactly `buf.len()` bytes. Returns Ok(true) when the buffer was filled,
+// Ok(false) on a clean EOF, and Err on an I/O error.
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> std::io::Result<bool> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => return Ok(false),
+            Ok(n) => filled += n,
+            Err(ref e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(true)
+}
+
 struct FfmpegOutput {
-    _stderr: Vec<u8>,
+    stderr: Vec<u8>,
     stdout: Vec<u8>,
 }
 
@@ -322,6 +1018,8 @@ fn run_ffmpeg_command(
     name: FfmpegCommandName,
     args: &[&OsStr],
     stderr_null: bool,
+    timeout_secs: usize,
+    stdin_source: Option<Box<dyn Read + Send>>,
 ) -> FfmpegCmdResult {
     fn truncate_ffmpeg_err_msg(stderr: Vec<u8>) -> FfmpegErrorKind {
         match std::str::from_utf8(&stderr) {
@@ -333,75 +1031,51 @@ fn run_ffmpeg_command(
     //Wait for the ffmpeg operation to complete FFMPEG_TIMEOUT_SECS
     let mut child = spawn_ffmpeg_command(name, args, stderr_null)?;
 
+    //when an input reader was supplied, stream it into the child's stdin on a
+    //dedicated thread (ffprobe/ffmpeg read `-` from stdin).
+    if let Some(mut reader) = stdin_source {
+        if let Some(mut stdin) = child.stdin.take() {
+            std::thread::spawn(move || {
+                let _copy_error = std::io::copy(&mut reader, &mut stdin);
+            });
+        }
+    }
+
     //Accumulators for output
     let mut stdout = child.stdout.take().expect("Failed to obtain stdout");
 
     let mut stderr = (!stderr_null).then(|| child.stderr.take().expect("Failed to obtain stderr"));
 
-    let mut timeout_counter_secs = 0;
-
     //We will assume that ffmpeg/ffprobe will usually complete in the first 1 sec. To keep this program responsive we will check for results at a rate of 100hz.
     //Then we will switch to checking at 1 Hz.
-    let thread = std::thread::spawn(move || -> std::io::Result<ExitStatus> {
-        let max_initial_fast_counts = 100;
-        let mut initial_fast_counts = 0;
-        let mut maybe_status;
-        while timeout_counter_secs < FFPROBE_TIMEOUT_SECS {
-            maybe_status = child.try_wait();
-            match maybe_status {
-                Err(e) => return Err(e),
-                Ok(None) => {
-                    if initial_fast_counts < max_initial_fast_counts {
-                        std::thread::sleep(Duration::from_millis(10));
-                        initial_fast_counts += 1;
-                        if initial_fast_counts == max_initial_fast_counts {
-                            timeout_counter_secs += 1;
-                        }
-                    } else {
-                        std::thread::sleep(Duration::from_millis(1_000));
-                        timeout_counter_secs += 1;
-                    }
-                }
-                Ok(Some(s)) => return Ok(s),
+    let thread = std::thread::spawn(move || wait_ffmpeg_child(child, timeout_secs));
+
+    //Drain stdout and stderr on separate threads. A single alternating blocking read
+    //would deadlock whenever one stream stays idle while the other fills its 64 KB
+    //pipe (e.g. the `-f null -` muxer writes nothing to stdout but logs per-frame to
+    //stderr) — ffmpeg blocks on the full pipe, never exits, and the idle read never
+    //returns. Reading each stream independently keeps both pipes drained.
+    fn drain(mut reader: impl Read) -> Vec<u8> {
+        let mut read_buf = [0u8; 4096];
+        let mut acc = vec![];
+        loop {
+            match reader.read(&mut read_buf) {
+                Err(_) | Ok(0) => break,
+                Ok(amount) => acc
+                    .write_all(&read_buf[..amount])
+                    .expect("failed to append to string"),
             }
         }
+        acc
+    }
 
-        //timed out
-        Err(std::io::Error::from(std::io::ErrorKind::TimedOut))
-    });
-
-    //read from stdout and stderr
-    let mut stdout_done = false;
-    let mut stderr_done = stderr_null;
-
-    //Buffer for stdout and stderr
-    let mut read_buf = [0u8; 4096];
-    let mut stdout_acc = vec![];
-    let mut stderr_acc = vec![];
-
-    while !(stdout_done && stderr_done) {
-        if !stdout_done {
-            match stdout.read(&mut read_buf) {
-                Err(_) | Ok(0) => stdout_done = true,
-                Ok(amount) => {
-                    stdout_acc
-                        .write_all(&read_buf[..amount])
-                        .expect("failed to append to string");
-                }
-            }
-        }
+    let stdout_thread = std::thread::spawn(move || drain(stdout));
+    let stderr_thread = stderr.map(|stderr| std::thread::spawn(move || drain(stderr)));
 
-        if !stderr_done {
-            match stderr.as_mut().unwrap().read(&mut read_buf) {
-                Err(_) | Ok(0) => stderr_done = true,
-                Ok(amount) => {
-                    stderr_acc
-                        .write_all(&read_buf[..amount])
-                        .expect("failed to append to string");
-                }
-            }
-        }
-    }
+    let stdout_acc = stdout_thread.join().expect("stdout thread couldn't join");
+    let stderr_acc = stderr_thread
+        .map(|t| t.join().expect("stderr thread couldn't join"))
+        .unwrap_or_default();
 
     let exit_status = thread.join().expect("thread couldn't join");
 
@@ -415,7 +1089,7 @@ fn run_ffmpeg_command(
             if status.success() {
                 Ok(FfmpegOutput {
                     stdout: stdout_acc,
-                    _stderr: stderr_acc,
+                    stderr: stderr_acc,
                 })
             } else {
                 //sometimes ffmpeg creates very long error messages. Limit them to the first 500 characters
@@ -58,6 +58,12 @@ pub struct VideoInfo {
     pub bit_rate: u32,
     pub resolution: (u32, u32),
     pub has_audio: bool,
+    pub frame_rate: f64,
+    pub frame_count: u64,
+    pub pix_fmt: String,
+    pub color_transfer: Option<String>,
+    pub color_primaries: Option<String>,
+    pub color_space: Option<String>,
 }
 
 impl VideoInfo {
@@ -67,7 +73,7 @@ impl VideoInfo {
     {
         use serde_json::Value;
 
-        let stats_string = get_video_stats(&src_path)?;
+        let stats_string = get_video_stats(src_path.as_ref())?;
         let stats_parsed: Value = serde_json::from_str(&stats_string).map_err(VideoInfoError::from)?;
 
         let duration = &stats_parsed["format"]["duration"];
@@ -125,6 +131,31 @@ impl VideoInfo {
             all_matched_values.iter().cloned().next()
         }
 
+        fn first_string_from_video_streams(stats_parsed: &Value, field_name: &str) -> Option<String> {
+            let video_streams = streams_of_type(stats_parsed, "video")?;
+
+            video_streams.iter().find_map(|stream| {
+                if let Value::String(v) = &stream[field_name] {
+                    Some(v.clone())
+                } else {
+                    None
+                }
+            })
+        }
+
+        // ffprobe reports frame rate as a rational string such as "30000/1001".
+        // A zero denominator (or the "0/0" placeholder ffmpeg emits for unknown
+        // rates) yields 0.0 rather than an error.
+        fn parse_frame_rate(rational: &str) -> f64 {
+            let mut parts = rational.split('/');
+            let num = parts.next().and_then(|n| n.trim().parse::<i64>().ok());
+            let den = parts.next().and_then(|d| d.trim().parse::<i64>().ok());
+            match (num, den) {
+                (Some(num), Some(den)) if den != 0 => num as f64 / den as f64,
+                _ => 0.0,
+            }
+        }
+
         // If the video metadata declares that a video is rotated, then FFMPEG will conveniently autorotate
         // each frame for us, however we will have to remember to swap around x and y axis if the rotation is
         // 90 or 270
@@ -197,12 +228,37 @@ impl VideoInfo {
                 }),
         };
 
+        let frame_rate = first_string_from_video_streams(&stats_parsed, "avg_frame_rate")
+            .filter(|s| s != "0/0")
+            .or_else(|| first_string_from_video_streams(&stats_parsed, "r_frame_rate"))
+            .map(|s| parse_frame_rate(&s))
+            .unwrap_or(0.0);
+
+        // Prefer the container-reported frame count, falling back to an estimate
+        // from duration when ffprobe leaves it absent or "N/A".
+        let frame_count = match first_string_from_video_streams(&stats_parsed, "nb_frames") {
+            Some(ref s) if s != "N/A" => s.parse().unwrap_or(0),
+            _ => (duration * frame_rate).round() as u64,
+        };
+
+        let pix_fmt =
+            first_string_from_video_streams(&stats_parsed, "pix_fmt").unwrap_or_default();
+        let color_transfer = first_string_from_video_streams(&stats_parsed, "color_transfer");
+        let color_primaries = first_string_from_video_streams(&stats_parsed, "color_primaries");
+        let color_space = first_string_from_video_streams(&stats_parsed, "color_space");
+
         Ok(VideoInfo {
             duration,
             size,
             bit_rate,
             resolution,
             has_audio,
+            frame_rate,
+            frame_count,
+            pix_fmt,
+            color_transfer,
+            color_primaries,
+            color_space,
         })
     }
 
@@ -221,4 +277,34 @@ impl VideoInfo {
     pub fn has_audio(&self) -> bool {
         self.has_audio
     }
+    pub fn frame_rate(&self) -> f64 {
+        self.frame_rate
+    }
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+    pub fn pix_fmt(&self) -> &str {
+        &self.pix_fmt
+    }
+    pub fn color_transfer(&self) -> Option<&str> {
+        self.color_transfer.as_deref()
+    }
+    pub fn color_primaries(&self) -> Option<&str> {
+        self.color_primaries.as_deref()
+    }
+    pub fn color_space(&self) -> Option<&str> {
+        self.color_space.as_deref()
+    }
+
+    // True when the stream declares an HDR transfer function (PQ or HLG) or the
+    // BT.2020 wide-gamut primaries, giving callers a single flag to branch on for
+    // tone-mapping decisions.
+    pub fn is_hdr(&self) -> bool {
+        let hdr_transfer = matches!(
+            self.color_transfer.as_deref(),
+            Some("smpte2084") | Some("arib-std-b67")
+        );
+        let wide_primaries = self.color_primaries.as_deref() == Some("bt2020");
+        hdr_transfer || wide_primaries
+    }
 }
@@ -1,6 +1,9 @@
+use std::borrow::Cow;
+use std::io::{Cursor, Write};
+
 use image::{
     imageops::{resize, FilterType::Lanczos3},
-    GenericImageView, GrayImage, RgbImage,
+    GenericImageView, GrayImage, Rgb, RgbImage,
 };
 
 #[derive(Debug, Clone)]
@@ -8,6 +11,27 @@ pub struct VideoFrames {
     frames: Vec<RgbImage>,
 }
 
+// Which matte colours count as letterbox/pillarbox bars.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LetterboxMode {
+    // Near-black or near-white bars: a pixel's channel sum must lie within three
+    // times the given tolerance of 0 or of 3 * 255.
+    BlackWhite(u32),
+    // Any uniformly coloured bar matching the frame's corner colour, within the
+    // given per-channel tolerance.
+    AnyColour(u32),
+}
+
+// Configuration for `VideoFrames::without_letterbox_with`.
+#[derive(Clone, Copy, Debug)]
+pub struct LetterboxConfig {
+    pub mode: LetterboxMode,
+    // Reject a detected strip run on a side if it would remove more than this
+    // fraction of that dimension, guarding against solid-colour fade frames
+    // collapsing the whole image.
+    pub max_crop_fraction: f32,
+}
+
 impl VideoFrames {
     pub fn from_images(images: &[RgbImage]) -> Self {
         Self {
@@ -15,14 +39,20 @@ impl VideoFrames {
         }
     }
 
+    // Crop letterbox/pillarbox bars using the default near-black/near-white
+    // detection (tolerance 16) with no crop-fraction limit.
     pub fn without_letterbox(&self) -> Self {
+        self.without_letterbox_with(LetterboxConfig {
+            mode: LetterboxMode::BlackWhite(16),
+            max_crop_fraction: 1.0,
+        })
+    }
+
+    // Crop letterbox/pillarbox bars according to `cfg`. The per-frame crop is
+    // unioned across every frame (a side is only trimmed where every frame agrees).
+    pub fn without_letterbox_with(&self, cfg: LetterboxConfig) -> Self {
         type RgbView<'a> = image::SubImage<&'a RgbImage>;
-        enum LetterboxColour {
-            BlackWhite(u32),
-            _AnyColour(u32),
-        }
-        use LetterboxColour::*;
-        let cfg: LetterboxColour = BlackWhite(16);
+        use LetterboxMode::*;
 
         enum Side {
             Left,
@@ -88,8 +118,12 @@ impl VideoFrames {
             }
         }
 
-        fn measure_frame(frame: &RgbView, colour: &LetterboxColour) -> Crop {
+        fn measure_frame(frame: &RgbView, cfg: &LetterboxConfig) -> Crop {
             let (width, height) = frame.dimensions();
+
+            //reference corner colour used to anchor `AnyColour` detection.
+            let image::Rgb::<u8>([corner_r, corner_g, corner_b]) = frame.get_pixel(0, 0);
+
             let measure_side = |side: Side| -> u32 {
                 //get the window of pixels representing the next row/column to be checked
                 let pixel_window = |idx: u32| -> RgbView {
@@ -105,7 +139,7 @@ impl VideoFrames {
                 };
 
                 let is_letterbox = |strip: &RgbView| -> bool {
-                    match colour {
+                    match cfg.mode {
                         BlackWhite(tol) => {
                             strip.pixels().all(|(_x, _y, image::Rgb::<u8>([r, g, b]))| {
                                 let black_enough = r as u32 + g as u32 + b as u32 <= tol * 3;
@@ -114,7 +148,7 @@ impl VideoFrames {
                                 black_enough || white_enough
                             })
                         }
-                        _AnyColour(tol) => {
+                        AnyColour(tol) => {
                             //calculate range
                             let (mut min_r, mut min_g, mut min_b) = (u8::MAX, u8::MAX, u8::MAX);
                             let (mut max_r, mut max_g, mut max_b) = (u8::MIN, u8::MIN, u8::MIN);
@@ -136,17 +170,34 @@ impl VideoFrames {
                                 max_b.saturating_sub(min_b) as u32,
                             );
 
-                            range_r + range_g + range_b <= tol * 3
+                            //the strip must be internally uniform (existing range test)
+                            //and match the frame's corner colour (the anchor).
+                            let uniform = range_r + range_g + range_b <= tol * 3;
+                            let matches_corner = min_r.abs_diff(corner_r) as u32 <= tol
+                                && max_r.abs_diff(corner_r) as u32 <= tol
+                                && min_g.abs_diff(corner_g) as u32 <= tol
+                                && max_g.abs_diff(corner_g) as u32 <= tol
+                                && min_b.abs_diff(corner_b) as u32 <= tol
+                                && max_b.abs_diff(corner_b) as u32 <= tol;
+
+                            uniform && matches_corner
                         }
                     }
                 };
 
-                let pix_range = match side {
-                    Left | Right => 0..width,
-                    Top | Bottom => 0..height,
+                let (pix_range, dimension) = match side {
+                    Left | Right => (0..width, width),
+                    Top | Bottom => (0..height, height),
                 };
 
-                pix_range.map(pixel_window).take_while(is_letterbox).count() as u32
+                let run = pix_range.map(pixel_window).take_while(is_letterbox).count() as u32;
+
+                //reject a run that would trim more than the allowed fraction.
+                if run as f32 > cfg.max_crop_fraction * dimension as f32 {
+                    0
+                } else {
+                    run
+                }
             };
 
             Crop::new(
@@ -222,6 +273,436 @@ impl VideoFrames {
             })
             .sum()
     }
+
+    // Encode the decoded frames as a single animated GIF. GIF is limited to 256
+    // colours, so a global palette is built across every frame with median-cut and
+    // each pixel is mapped to it using Floyd–Steinberg error diffusion rather than
+    // naive truncation. `delay_centiseconds` is the per-frame delay and `loop_mode`
+    // controls looping. Returns an empty buffer if there are no frames or encoding
+    // fails.
+    pub fn to_animated_gif(&self, delay_centiseconds: u16, loop_mode: gif::Repeat) -> Vec<u8> {
+        if self.frames.is_empty() {
+            return Vec::new();
+        }
+
+        let palette = build_global_palette(&self.frames, 256);
+
+        //flatten and pad the palette to a full 256-colour table (the extra entries
+        //are never referenced).
+        let mut global_palette = Vec::with_capacity(256 * 3);
+        for colour in &palette {
+            global_palette.extend_from_slice(colour);
+        }
+        global_palette.resize(256 * 3, 0);
+
+        let (width, height) = (self.frames[0].width() as u16, self.frames[0].height() as u16);
+
+        let mut out = Vec::new();
+        {
+            let mut encoder = match gif::Encoder::new(&mut out, width, height, &global_palette) {
+                Ok(encoder) => encoder,
+                Err(_) => return Vec::new(),
+            };
+
+            if encoder.set_repeat(loop_mode).is_err() {
+                return Vec::new();
+            }
+
+            for frame in &self.frames {
+                let indices = dither_to_indices(frame, &palette);
+
+                let mut gif_frame = gif::Frame::default();
+                gif_frame.width = frame.width() as u16;
+                gif_frame.height = frame.height() as u16;
+                gif_frame.delay = delay_centiseconds;
+                gif_frame.buffer = Cow::Owned(indices);
+
+                if encoder.write_frame(&gif_frame).is_err() {
+                    return Vec::new();
+                }
+            }
+        }
+
+        out
+    }
+
+    // Suppress inter-frame noise the way GIF optimizers do: walk the frames in
+    // order keeping a running previous frame, and wherever a pixel's absolute luma
+    // difference from that previous frame is below `threshold`, carry the previous
+    // pixel forward instead of the new one. Static background regions therefore
+    // become byte-identical across frames, which stabilizes the `png_size`
+    // fingerprint against capture/encode jitter. The first frame is the untouched
+    // anchor.
+    pub fn denoise_temporal(&self, threshold: u8) -> Self {
+        if self.frames.is_empty() {
+            return Self { frames: vec![] };
+        }
+
+        let mut out: Vec<RgbImage> = Vec::with_capacity(self.frames.len());
+        out.push(self.frames[0].clone());
+
+        for current in &self.frames[1..] {
+            let prev = out.last().unwrap();
+            let mut denoised = current.clone();
+
+            for (out_pixel, (prev_pixel, cur_pixel)) in denoised
+                .pixels_mut()
+                .zip(prev.pixels().zip(current.pixels()))
+            {
+                let diff = luma(cur_pixel) as i32 - luma(prev_pixel) as i32;
+                if diff.unsigned_abs() < threshold as u32 {
+                    *out_pixel = *prev_pixel;
+                }
+            }
+
+            out.push(denoised);
+        }
+
+        Self { frames: out }
+    }
+
+    // Render a grid of frames directly into a graphics-capable terminal so someone
+    // triaging duplicates over SSH can eyeball clips without opening files. The
+    // protocol is detected from `$KITTY_WINDOW_ID`/`$TERM`; each frame is resized
+    // to `target_cell_px` and a newline is emitted after every `cols` frames.
+    // Returns an `Unsupported` error when neither kitty nor sixel is available.
+    pub fn write_terminal_montage(
+        &self,
+        out: &mut impl Write,
+        cols: u32,
+        target_cell_px: (u32, u32),
+    ) -> std::io::Result<()> {
+        let graphics = detect_terminal_graphics();
+        if matches!(graphics, TerminalGraphics::Unsupported) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "no supported terminal graphics protocol detected",
+            ));
+        }
+
+        let (cell_w, cell_h) = target_cell_px;
+        let cols = cols.max(1);
+
+        for (i, frame) in self.frames.iter().enumerate() {
+            let cell = resize(frame, cell_w, cell_h, Lanczos3);
+            match graphics {
+                TerminalGraphics::Kitty => write_kitty_image(out, &cell)?,
+                TerminalGraphics::Sixel => write_sixel_image(out, &cell)?,
+                TerminalGraphics::Unsupported => unreachable!(),
+            }
+            if (i as u32 + 1) % cols == 0 {
+                out.write_all(b"\n")?;
+            }
+        }
+        out.write_all(b"\n")?;
+
+        Ok(())
+    }
+}
+
+enum TerminalGraphics {
+    Kitty,
+    Sixel,
+    Unsupported,
+}
+
+fn detect_terminal_graphics() -> TerminalGraphics {
+    if std::env::var_os("KITTY_WINDOW_ID").is_some() {
+        return TerminalGraphics::Kitty;
+    }
+    match std::env::var("TERM") {
+        Ok(term) if term.contains("kitty") => TerminalGraphics::Kitty,
+        Ok(term) if term.contains("sixel") => TerminalGraphics::Sixel,
+        _ => TerminalGraphics::Unsupported,
+    }
+}
+
+// Standard base64 encoding (used for the kitty transmission payload).
+fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b0 = chunk[0] as u32;
+        let b1 = *chunk.get(1).unwrap_or(&0) as u32;
+        let b2 = *chunk.get(2).unwrap_or(&0) as u32;
+        let n = (b0 << 16) | (b1 << 8) | b2;
+
+        out.push(ALPHABET[(n >> 18 & 0x3f) as usize] as char);
+        out.push(ALPHABET[(n >> 12 & 0x3f) as usize] as char);
+        if chunk.len() > 1 {
+            out.push(ALPHABET[(n >> 6 & 0x3f) as usize] as char);
+        } else {
+            out.push('=');
+        }
+        if chunk.len() > 2 {
+            out.push(ALPHABET[(n & 0x3f) as usize] as char);
+        } else {
+            out.push('=');
+        }
+    }
+    out
+}
+
+// Transmit-and-display a single image with the kitty graphics protocol: PNG-encode,
+// base64, and split into 4096-byte chunks wrapped in APC sequences, flagging all
+// but the final chunk with the `m=1` continuation.
+fn write_kitty_image(out: &mut impl Write, img: &RgbImage) -> std::io::Result<()> {
+    let mut png = Vec::new();
+    img.write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+
+    let encoded = base64_encode(&png);
+    let chunks: Vec<&[u8]> = encoded.as_bytes().chunks(4096).collect();
+    let last_index = chunks.len().saturating_sub(1);
+
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = if i == last_index { 0 } else { 1 };
+        if i == 0 {
+            write!(out, "\x1b_Gf=100,a=T,m={};", more)?;
+        } else {
+            write!(out, "\x1b_Gm={};", more)?;
+        }
+        out.write_all(chunk)?;
+        out.write_all(b"\x1b\\")?;
+    }
+
+    Ok(())
+}
+
+// Emit a single image as sixel graphics, quantizing to a <=256 colour palette via
+// median-cut and banding the image into rows of six pixels, where each column's
+// vertical bitmask is encoded as `0x3f + mask`.
+fn write_sixel_image(out: &mut impl Write, img: &RgbImage) -> std::io::Result<()> {
+    let (width, height) = img.dimensions();
+
+    let pixels: Vec<[u8; 3]> = img.pixels().map(|&Rgb([r, g, b])| [r, g, b]).collect();
+    let palette = median_cut(pixels, 256);
+
+    //map every pixel to its nearest palette entry up front.
+    let indices: Vec<usize> = img
+        .pixels()
+        .map(|&Rgb([r, g, b])| nearest_palette_index(&[r as i32, g as i32, b as i32], &palette))
+        .collect();
+
+    //introducer.
+    out.write_all(b"\x1bP0;0;0q")?;
+
+    //palette definitions in sixel's 0..=100 colour space.
+    for (n, colour) in palette.iter().enumerate() {
+        let sr = (colour[0] as u32 * 100 + 127) / 255;
+        let sg = (colour[1] as u32 * 100 + 127) / 255;
+        let sb = (colour[2] as u32 * 100 + 127) / 255;
+        write!(out, "#{};2;{};{};{}", n, sr, sg, sb)?;
+    }
+
+    let mut band_top = 0;
+    while band_top < height {
+        for n in 0..palette.len() {
+            write!(out, "#{}", n)?;
+            for x in 0..width {
+                let mut mask = 0u8;
+                for row in 0..6 {
+                    let y = band_top + row;
+                    if y < height && indices[(y * width + x) as usize] == n {
+                        mask |= 1 << row;
+                    }
+                }
+                out.write_all(&[0x3f + mask])?;
+            }
+            //graphics carriage return: overlay the next colour on the same band.
+            out.write_all(b"$")?;
+        }
+        //graphics newline: advance to the next six-pixel band.
+        out.write_all(b"-")?;
+        band_top += 6;
+    }
+
+    //string terminator.
+    out.write_all(b"\x1b\\")?;
+
+    Ok(())
+}
+
+// BT.601 luma of an RGB pixel.
+fn luma(pixel: &Rgb<u8>) -> u8 {
+    let Rgb([r, g, b]) = *pixel;
+    ((r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000) as u8
+}
+
+// Cap on the number of pixels median-cut runs over. Collecting every pixel of
+// every frame is O(total-pixels) in both memory and time, which can reach
+// hundreds of MB (and OOM) on a whole-file decode; sampling to this budget keeps
+// the palette representative without blowing up on a long clip.
+const PALETTE_SAMPLE_BUDGET: usize = 1 << 20;
+
+// Build a single <=`max_colors` global palette from a sample of the pixels of all
+// frames using median-cut: repeatedly split the colour box with the largest
+// channel range at its median until the target count is reached or no box can be
+// split further, then average each box to a palette entry.
+fn build_global_palette(frames: &[RgbImage], max_colors: usize) -> Vec<[u8; 3]> {
+    let total_pixels: usize = frames.iter().map(|f| f.pixels().len()).sum();
+
+    //take every `stride`th pixel across the whole decode so the sample stays under
+    //the budget while still spanning every frame.
+    let stride = (total_pixels / PALETTE_SAMPLE_BUDGET).max(1);
+
+    let mut pixels = Vec::new();
+    for (i, &Rgb([r, g, b])) in frames.iter().flat_map(|frame| frame.pixels()).enumerate() {
+        if i % stride == 0 {
+            pixels.push([r, g, b]);
+        }
+    }
+    median_cut(pixels, max_colors)
+}
+
+// Return the channel (0=r, 1=g, 2=b) with the widest value range in `box_pixels`,
+// together with that range.
+fn widest_channel(box_pixels: &[[u8; 3]]) -> (usize, u32) {
+    let mut min = [u8::MAX; 3];
+    let mut max = [u8::MIN; 3];
+    for pixel in box_pixels {
+        for c in 0..3 {
+            if pixel[c] < min[c] {
+                min[c] = pixel[c];
+            }
+            if pixel[c] > max[c] {
+                max[c] = pixel[c];
+            }
+        }
+    }
+
+    let mut channel = 0;
+    let mut range = 0u32;
+    for c in 0..3 {
+        let r = (max[c] - min[c]) as u32;
+        if r > range {
+            range = r;
+            channel = c;
+        }
+    }
+    (channel, range)
+}
+
+fn average_colour(box_pixels: &[[u8; 3]]) -> [u8; 3] {
+    if box_pixels.is_empty() {
+        return [0, 0, 0];
+    }
+    let mut sum = [0u64; 3];
+    for pixel in box_pixels {
+        for c in 0..3 {
+            sum[c] += pixel[c] as u64;
+        }
+    }
+    let len = box_pixels.len() as u64;
+    [
+        (sum[0] / len) as u8,
+        (sum[1] / len) as u8,
+        (sum[2] / len) as u8,
+    ]
+}
+
+fn median_cut(pixels: Vec<[u8; 3]>, max_colors: usize) -> Vec<[u8; 3]> {
+    if pixels.is_empty() {
+        return vec![[0, 0, 0]];
+    }
+
+    let mut boxes = vec![pixels];
+
+    while boxes.len() < max_colors {
+        //pick the splittable box with the widest channel range.
+        let mut target = None;
+        let mut best_range = 0u32;
+        for (i, box_pixels) in boxes.iter().enumerate() {
+            if box_pixels.len() < 2 {
+                continue;
+            }
+            let (_, range) = widest_channel(box_pixels);
+            if range > best_range {
+                best_range = range;
+                target = Some(i);
+            }
+        }
+
+        let idx = match target {
+            Some(idx) => idx,
+            None => break,
+        };
+
+        let mut box_pixels = boxes.remove(idx);
+        let (channel, _) = widest_channel(&box_pixels);
+        box_pixels.sort_by_key(|pixel| pixel[channel]);
+        let upper = box_pixels.split_off(box_pixels.len() / 2);
+        boxes.push(box_pixels);
+        boxes.push(upper);
+    }
+
+    boxes.iter().map(|b| average_colour(b)).collect()
+}
+
+// Index of the palette entry nearest `colour` by squared euclidean distance.
+fn nearest_palette_index(colour: &[i32; 3], palette: &[[u8; 3]]) -> usize {
+    let mut best = 0;
+    let mut best_dist = i32::MAX;
+    for (i, entry) in palette.iter().enumerate() {
+        let dr = colour[0] - entry[0] as i32;
+        let dg = colour[1] - entry[1] as i32;
+        let db = colour[2] - entry[2] as i32;
+        let dist = dr * dr + dg * dg + db * db;
+        if dist < best_dist {
+            best_dist = dist;
+            best = i;
+        }
+    }
+    best
+}
+
+// Map a frame to palette indices using Floyd–Steinberg error diffusion (7/16
+// right, 3/16 below-left, 5/16 below, 1/16 below-right).
+fn dither_to_indices(frame: &RgbImage, palette: &[[u8; 3]]) -> Vec<u8> {
+    let width = frame.width() as i64;
+    let height = frame.height() as i64;
+
+    let mut buf: Vec<[i32; 3]> = frame
+        .pixels()
+        .map(|&Rgb([r, g, b])| [r as i32, g as i32, b as i32])
+        .collect();
+    let mut indices = vec![0u8; buf.len()];
+
+    for y in 0..height {
+        for x in 0..width {
+            let here = (y * width + x) as usize;
+            let old = buf[here];
+            let clamped = [
+                old[0].clamp(0, 255),
+                old[1].clamp(0, 255),
+                old[2].clamp(0, 255),
+            ];
+
+            let pi = nearest_palette_index(&clamped, palette);
+            indices[here] = pi as u8;
+
+            let chosen = palette[pi];
+            let err = [
+                old[0] - chosen[0] as i32,
+                old[1] - chosen[1] as i32,
+                old[2] - chosen[2] as i32,
+            ];
+
+            for &(dx, dy, num) in &[(1i64, 0i64, 7), (-1, 1, 3), (0, 1, 5), (1, 1, 1)] {
+                let (xx, yy) = (x + dx, y + dy);
+                if xx >= 0 && xx < width && yy >= 0 && yy < height {
+                    let there = (yy * width + xx) as usize;
+                    for c in 0..3 {
+                        buf[there][c] += err[c] * num / 16;
+                    }
+                }
+            }
+        }
+    }
+
+    indices
 }
 
 pub struct GrayFramifiedVideo {
@@ -250,3 +731,284 @@ impl GrayFramifiedVideo {
         self.frames
     }
 }
+
+// Chroma subsampling of a planar frame: how far the chroma planes are shrunk
+// relative to luma (`x_shift`/`y_shift` as right-shift amounts) and the byte
+// stride between successive samples of a component (`1` for planar layouts).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Subsampling {
+    pub x_shift: u32,
+    pub y_shift: u32,
+    pub pixel_stride: u32,
+}
+
+impl Subsampling {
+    pub fn yuv420() -> Self {
+        Self {
+            x_shift: 1,
+            y_shift: 1,
+            pixel_stride: 1,
+        }
+    }
+}
+
+// A single component plane with its own dimensions and row stride.
+#[derive(Clone, Debug)]
+pub struct YuvPlane {
+    pub data: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub stride: u32,
+}
+
+impl YuvPlane {
+    pub fn new(data: Vec<u8>, width: u32, height: u32, stride: u32) -> Self {
+        Self {
+            data,
+            width,
+            height,
+            stride,
+        }
+    }
+
+    fn at(&self, x: u32, y: u32) -> u8 {
+        self.data[(y * self.stride + x) as usize]
+    }
+}
+
+// A planar Y/U/V frame as handed over by ffmpeg, keeping the planes separate so
+// luma-only work can touch the Y plane without an RGB round-trip.
+#[derive(Clone, Debug)]
+pub struct YuvFrame {
+    pub y: YuvPlane,
+    pub u: YuvPlane,
+    pub v: YuvPlane,
+    pub subsampling: Subsampling,
+}
+
+impl YuvFrame {
+    pub fn new(y: YuvPlane, u: YuvPlane, v: YuvPlane, subsampling: Subsampling) -> Self {
+        Self {
+            y,
+            u,
+            v,
+            subsampling,
+        }
+    }
+}
+
+// Which YUV->RGB matrix to use when a conversion to `VideoFrames` is finally
+// required.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum YuvMatrix {
+    Bt601,
+    Bt709,
+}
+
+#[derive(Clone, Debug)]
+pub struct YuvFrames {
+    frames: Vec<YuvFrame>,
+}
+
+impl YuvFrames {
+    pub fn from_frames(frames: Vec<YuvFrame>) -> Self {
+        Self { frames }
+    }
+
+    pub fn into_inner(self) -> Vec<YuvFrame> {
+        self.frames
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    // Crop away near-black/near-white letterbox/pillarbox bars. The test runs
+    // directly on the luma plane — a strip is matte if every sample lies in
+    // `[16, 16 + tol]` or `[235 - tol, 235]` — and the crop is the union across
+    // every frame, matching `VideoFrames::without_letterbox`.
+    pub fn without_letterbox(&self) -> Self {
+        const TOL: u8 = 16;
+
+        let crop = self
+            .frames
+            .iter()
+            .map(|frame| measure_luma_letterbox(&frame.y, TOL))
+            .reduce(|a, b| {
+                (
+                    a.0.min(b.0),
+                    a.1.min(b.1),
+                    a.2.min(b.2),
+                    a.3.min(b.3),
+                )
+            });
+
+        let (left, right, top, bottom) = match crop {
+            Some(crop) => crop,
+            None => return self.clone(),
+        };
+
+        let frames = self
+            .frames
+            .iter()
+            .map(|frame| {
+                let ss = frame.subsampling;
+                YuvFrame::new(
+                    crop_plane(&frame.y, left, right, top, bottom),
+                    crop_plane(
+                        &frame.u,
+                        left >> ss.x_shift,
+                        right >> ss.x_shift,
+                        top >> ss.y_shift,
+                        bottom >> ss.y_shift,
+                    ),
+                    crop_plane(
+                        &frame.v,
+                        left >> ss.x_shift,
+                        right >> ss.x_shift,
+                        top >> ss.y_shift,
+                        bottom >> ss.y_shift,
+                    ),
+                    ss,
+                )
+            })
+            .collect();
+
+        Self { frames }
+    }
+
+    // Plane-aware resize: the luma plane is scaled to `(width, height)` and the
+    // chroma planes to the subsampled dimensions (half resolution for yuv420).
+    pub fn resize(&self, width: u32, height: u32) -> Self {
+        let frames = self
+            .frames
+            .iter()
+            .map(|frame| {
+                let ss = frame.subsampling;
+                let cw = (width + (1 << ss.x_shift) - 1) >> ss.x_shift;
+                let ch = (height + (1 << ss.y_shift) - 1) >> ss.y_shift;
+                YuvFrame::new(
+                    resize_plane(&frame.y, width, height),
+                    resize_plane(&frame.u, cw, ch),
+                    resize_plane(&frame.v, cw, ch),
+                    ss,
+                )
+            })
+            .collect();
+
+        Self { frames }
+    }
+
+    // Convert to packed RGB `VideoFrames` using the selected matrix. Only call this
+    // when RGB output is actually needed — the luma pipeline is cheaper otherwise.
+    pub fn to_video_frames(&self, matrix: YuvMatrix) -> VideoFrames {
+        let frames = self
+            .frames
+            .iter()
+            .map(|frame| yuv_frame_to_rgb(frame, matrix))
+            .collect();
+
+        VideoFrames { frames }
+    }
+}
+
+// Measure the letterbox/pillarbox crop on a luma plane, returning (left, right,
+// top, bottom). A side run is rejected if it would consume the whole dimension.
+fn measure_luma_letterbox(y: &YuvPlane, tol: u8) -> (u32, u32, u32, u32) {
+    let (width, height) = (y.width, y.height);
+    let tol = tol as u32;
+
+    let is_matte = |luma: u8| -> bool {
+        let l = luma as u32;
+        (l >= 16 && l <= 16 + tol) || (l >= 235u32.saturating_sub(tol) && l <= 235)
+    };
+    let column_matte = |x: u32| (0..height).all(|yy| is_matte(y.at(x, yy)));
+    let row_matte = |yy: u32| (0..width).all(|x| is_matte(y.at(x, yy)));
+
+    let left = (0..width).take_while(|&x| column_matte(x)).count() as u32;
+    let right = (0..width).rev().take_while(|&x| column_matte(x)).count() as u32;
+    let top = (0..height).take_while(|&yy| row_matte(yy)).count() as u32;
+    let bottom = (0..height).rev().take_while(|&yy| row_matte(yy)).count() as u32;
+
+    let (left, right) = if left + right < width {
+        (left, right)
+    } else {
+        (0, 0)
+    };
+    let (top, bottom) = if top + bottom < height {
+        (top, bottom)
+    } else {
+        (0, 0)
+    };
+
+    (left, right, top, bottom)
+}
+
+fn crop_plane(plane: &YuvPlane, left: u32, right: u32, top: u32, bottom: u32) -> YuvPlane {
+    let new_width = plane.width.saturating_sub(left + right);
+    let new_height = plane.height.saturating_sub(top + bottom);
+
+    let mut data = Vec::with_capacity((new_width * new_height) as usize);
+    for yy in top..top + new_height {
+        for x in left..left + new_width {
+            data.push(plane.at(x, yy));
+        }
+    }
+
+    YuvPlane::new(data, new_width, new_height, new_width)
+}
+
+fn resize_plane(plane: &YuvPlane, new_width: u32, new_height: u32) -> YuvPlane {
+    if new_width == 0 || new_height == 0 || plane.width == 0 || plane.height == 0 {
+        return YuvPlane::new(vec![], new_width, new_height, new_width);
+    }
+
+    //nearest-neighbour resample, which is adequate for the fingerprinting path.
+    let mut data = Vec::with_capacity((new_width * new_height) as usize);
+    for yy in 0..new_height {
+        let sy = (yy * plane.height / new_height).min(plane.height - 1);
+        for x in 0..new_width {
+            let sx = (x * plane.width / new_width).min(plane.width - 1);
+            data.push(plane.at(sx, sy));
+        }
+    }
+
+    YuvPlane::new(data, new_width, new_height, new_width)
+}
+
+fn yuv_frame_to_rgb(frame: &YuvFrame, matrix: YuvMatrix) -> RgbImage {
+    let (width, height) = (frame.y.width, frame.y.height);
+    let ss = frame.subsampling;
+
+    RgbImage::from_fn(width, height, |x, y| {
+        let cx = (x >> ss.x_shift).min(frame.u.width.saturating_sub(1));
+        let cy = (y >> ss.y_shift).min(frame.u.height.saturating_sub(1));
+
+        let yf = frame.y.at(x, y) as f32;
+        let uf = frame.u.at(cx, cy) as f32 - 128.0;
+        let vf = frame.v.at(cx, cy) as f32 - 128.0;
+
+        let (r, g, b) = match matrix {
+            YuvMatrix::Bt601 => (
+                yf + 1.402 * vf,
+                yf - 0.344136 * uf - 0.714136 * vf,
+                yf + 1.772 * uf,
+            ),
+            YuvMatrix::Bt709 => (
+                yf + 1.5748 * vf,
+                yf - 0.1873 * uf - 0.4681 * vf,
+                yf + 1.8556 * uf,
+            ),
+        };
+
+        Rgb([clamp_u8(r), clamp_u8(g), clamp_u8(b)])
+    })
+}
+
+fn clamp_u8(v: f32) -> u8 {
+    v.round().clamp(0.0, 255.0) as u8
+}